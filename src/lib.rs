@@ -1,56 +1,101 @@
 // NOTE: All imports used by parse()
+use std::collections::{HashMap, VecDeque};
 use std::fs::File;
 use std::io::{BufRead, BufReader};
 use std::path::Path;
+use std::time::{Duration, Instant};
 
+use clap::ValueEnum;
 use regex::Regex;
+use serde::Serialize;
+
+/** Aggregate parse/construct timing and volume, accumulated across a
+directory walk when navigator is run with `timing: true` */
+#[derive(Default)]
+pub struct Stats {
+    pub files: usize,
+    pub headings: usize,
+    pub parse_time: Duration,
+    pub construct_time: Duration,
+}
+
+/** How navigator should render a file's table of contents: the default
+box-drawing tree, a machine-readable JSON/YAML document for piping into
+other tooling, or a nested <ul>/<li> HTML fragment for embedding in a
+rendered page */
+#[derive(Clone, Copy, Default, ValueEnum)]
+pub enum OutputFormat {
+    #[default]
+    Tree,
+    Json,
+    Yaml,
+    Html,
+}
 
 struct Heading {
     level: usize,
     title: String,
+    /// 1-based source line the heading's `#...` was found on;
+    /// 0 for synthetic headings (the tree root, "[]" placeholders)
+    line: usize,
 }
 impl Heading {
     /** Just a humble Heading builder */
-    fn new(title: String, level: usize) -> Heading {
-        Heading { level, title }
+    fn new(title: String, level: usize, line: usize) -> Heading {
+        Heading { level, title, line }
     }
 }
 
-/** A position in the tree as raw pointer to a Node, generic over T */
-type Pos<T> = Option<*mut Node<T>>;
-
-/** Represents a general tree with a collection of children */
-struct Node<T> {
-    parent: Pos<T>,
-    children: Vec<Pos<T>>,
-    data: Option<T>,
+/** A position in the tree as an index into the owning GenTree's arena,
+generic over T so a Pos<Heading> can't be mixed up with a position into
+some other tree. Carries no pointer, so it's Copy and never dangles. */
+struct Pos<T> {
+    index: usize,
+    _marker: std::marker::PhantomData<T>,
 }
-impl<T> Node<T> {
-    /** Builds a new Node and returns its position */
-    fn build(data: Option<T>) -> Box<Node<T>> {
-        Box::new(Node {
-            parent: None,
-            children: Vec::new(),
-            data,
-        })
-    }
-
-    /** Gets an immutable reference to the data at a position */
-    fn get<'a>(position: Pos<T>) -> Option<&'a T> {
-        if let Some(p) = position {
-            unsafe { (*p).data.as_ref() }
-        } else {
-            None
+impl<T> Pos<T> {
+    fn new(index: usize) -> Pos<T> {
+        Pos {
+            index,
+            _marker: std::marker::PhantomData,
         }
     }
 }
+// Manual impls since `derive` would incorrectly require T: Copy/Eq/etc.
+impl<T> Clone for Pos<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+impl<T> Copy for Pos<T> {}
+impl<T> PartialEq for Pos<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.index == other.index
+    }
+}
+impl<T> std::fmt::Debug for Pos<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Pos({})", self.index)
+    }
+}
 
-/** The Tree struct represents a positional, linked-based general
-tree structure with a root node that contains a single raw pointer
-to the root node and the structure's size.
+/** A single slot in a GenTree's arena: a node's parent/children
+expressed as arena indices instead of pointers */
+struct NodeEntry<T> {
+    parent: Option<Pos<T>>,
+    children: Vec<Pos<T>>,
+    data: Option<T>,
+}
+
+/** The Tree struct represents a positional, arena-based general
+tree structure with a root position and the structure's size.
+Nodes live in a single Vec so the whole tree is freed when the
+Vec is, and every traversal is a safe index walk instead of a
+raw-pointer dereference.
 The genericity of the struct means you'll have to explicitly
 type associated functions. */
 struct GenTree<T> {
+    arena: Vec<NodeEntry<T>>,
     root: Pos<T>,
     size: usize,
 }
@@ -58,57 +103,52 @@ impl<T> GenTree<T> {
 
     /** Instantiates a new Tree with a default root */
     fn new() -> GenTree<Heading> {
-        let data = Heading::new("ROOT".to_string(), 0);
-        let root: Pos<Heading> = Some(Box::into_raw(Node::build(Some(data))));
-        GenTree { root, size: 1 }
+        let data = Heading::new("ROOT".to_string(), 0, 0);
+        let arena = vec![NodeEntry {
+            parent: None,
+            children: Vec::new(),
+            data: Some(data),
+        }];
+        GenTree {
+            arena,
+            root: Pos::new(0),
+            size: 1,
+        }
+    }
+
+    /** Allocates a new node in the arena and returns its position */
+    fn build(&mut self, data: Option<T>) -> Pos<T> {
+        self.arena.push(NodeEntry {
+            parent: None,
+            children: Vec::new(),
+            data,
+        });
+        Pos::new(self.arena.len() - 1)
     }
 
     /** Returns an immutable reference to a position's data */
-    fn get(&self, node: &Pos<T>) -> Option<&T> {
-        // Imperative approach
-        if let Some(n) = node {
-            unsafe { (*(*n)).data.as_ref() } // Double de-ref for &*mut type
-        } else {
-            None
-        }
-        // Functional approach
-        //node.as_ref().and_then(|n| unsafe { (*(*n)).data.as_ref() })
+    fn get(&self, node: Pos<T>) -> Option<&T> {
+        self.arena[node.index].data.as_ref()
     }
 
     /** Returns the parent of a given node, if it exists */
     fn parent(&self, node: Pos<T>) -> Option<Pos<T>> {
-        if let Some(n) = node {
-            unsafe { Some((*n).parent) }
-        } else {
-            None
-        }
+        self.arena[node.index].parent
     }
 
     /** Adds a child to a parent's children field represented as Vec<Pos<T>> */
     fn add_child(&mut self, ancestor: Pos<T>, node: Pos<T>) {
-        unsafe {
-            if let Some(p) = ancestor {
-                // Adds the position to the parents arena
-                (*p).children.push(node);
-
-                // Links the node's parent Pos<T> to the correct ancestor
-                if let Some(n) = node {
-                    (*n).parent = ancestor;
-                }
-            }
-            self.size += 1;
-        }
+        self.arena[ancestor.index].children.push(node);
+        self.arena[node.index].parent = Some(ancestor);
+        self.size += 1;
     }
 
-    // NOTE: The rest of the methods in this impl block are just used in testing
+    // NOTE: The rest of the methods in this impl block are used by
+    // traversal (NodeIter) and testing
 
     /** Returns a reference to the collection of children for a given position, if any */
     fn _children(&self, node: Pos<T>) -> Option<&Vec<Pos<T>>> {
-        if let Some(c) = node {
-            Some(unsafe { (*c).children.as_ref() })
-        } else {
-            None
-        }
+        Some(&self.arena[node.index].children)
     }
 
     /** Returns true if the given position is the tree's root */
@@ -130,44 +170,170 @@ impl<T> GenTree<T> {
     /** Returns the height of a sub-tree at a given position */
     fn _height(&self, node: Pos<T>) -> Option<usize> {
         let mut h = 0;
-        if let Some(n) = node {
-            for e in unsafe { &(*n).children } {
-                h = std::cmp::max(h, self._height(Some(e.expect("uh oh")))?)
-            }
+        for &child in &self.arena[node.index].children {
+            h = std::cmp::max(h, self._height(child)?)
         }
         Some(h + 1)
     }
 
 }
 
-impl<T> Drop for GenTree<T> {
-    fn drop(&mut self) {
-        /** Recursive tree destructor */
-        // TODO: Update implementation with NonNull
-        // to avoid null pointer dereference check
-        unsafe fn drop_node_recursive<T>(node_ptr: *mut Node<T>) {
-            // Avoids a null pointer dereference
-            if node_ptr.is_null() {
-                return;
+impl GenTree<Heading> {
+    /** Returns a non-recursive preorder iterator over the tree, yielding
+    each node's depth (relative to `position`, whose children are at
+    depth 1) alongside its Heading. `position` is not itself yielded, so
+    callers can seed the walk from the tree's root or from any subtree
+    found via resolve_path. pretty_print uses it only to check whether a
+    subtree is empty; the box-drawing output (preorder), to_html and
+    to_toc_node each walk the tree recursively on their own. */
+    fn iter(&self, position: Pos<Heading>) -> NodeIter<'_> {
+        let mut queue = VecDeque::new();
+        if let Some(children) = self._children(position) {
+            for &child in children {
+                queue.push_back((child, 1));
             }
+        }
+        NodeIter { tree: self, queue }
+    }
 
-            // Dereference the pointer and process its children
-            let node = &mut *node_ptr;
-            for &child_ptr in node.children.iter() {
-                if let Some(child_ptr) = child_ptr {
-                    drop_node_recursive(child_ptr);
-                }
-            }
+    /** Recursively converts the subtree rooted at `position` into a
+    serializable TocNode tree; placeholder "[]" nodes are kept so the
+    emitted structure still reflects the original heading levels.
+    `file` is unset on every node here; the caller stamps it (and, for
+    the whole-document case, overrides the synthetic root title) on the
+    single outermost node before serializing. */
+    fn to_toc_node(&self, position: Pos<Heading>) -> TocNode {
+        let heading = self.get(position).expect("invalid position");
+        let children = self
+            ._children(position)
+            .expect("invalid position")
+            .iter()
+            .map(|&child| self.to_toc_node(child))
+            .collect();
+        TocNode {
+            title: heading.title.clone(),
+            level: heading.level,
+            line: heading.line,
+            file: None,
+            children,
+        }
+    }
 
-            // Deallocate the current node
-            let _ = Box::from_raw(node_ptr);
+    /** Recursively renders the subtree rooted at `position` as a nested
+    <ul>/<li> list, with each item linking to its heading's anchor slug;
+    `seen` tracks slugs already handed out so repeats get disambiguated */
+    fn to_html(&self, position: Pos<Heading>, seen: &mut HashMap<String, usize>) -> String {
+        let children = self._children(position).expect("invalid position");
+        if children.is_empty() {
+            return String::new();
         }
+        let mut html = String::from("<ul>\n");
+        for &child in children {
+            let heading = self.get(child).expect("missing data");
+            let slug = slugify(&heading.title, seen);
+            let line_link = if heading.line > 0 {
+                format!(" <a href=\"#L{0}\" class=\"line\">L{0}</a>", heading.line)
+            } else {
+                String::new()
+            };
+            html.push_str(&format!(
+                "<li><a href=\"#{}\">{}</a>{}{}</li>\n",
+                slug,
+                escape_html(&heading.title),
+                line_link,
+                self.to_html(child, seen)
+            ));
+        }
+        html.push_str("</ul>\n");
+        html
+    }
 
-        unsafe {
-            if let Some(root_ptr) = self.root {
-                drop_node_recursive(root_ptr);
-            }
+    /** Walks from the root matching each segment of `path` against a
+    child's title, returning the position of the final segment's match
+    if the whole path resolves; used by the --find flag to extract one
+    section's outline instead of dumping the whole file */
+    fn resolve_path(&self, path: &[&str]) -> Option<Pos<Heading>> {
+        let mut position = self.root;
+        for segment in path {
+            let children = self._children(position)?;
+            position = *children.iter().find(|&&child| {
+                self.get(child).map(|h| h.title == *segment).unwrap_or(false)
+            })?;
         }
+        Some(position)
+    }
+}
+
+/** Escapes the characters HTML requires escaped in text/attribute
+position (&, <, >, ") so a heading title can't inject markup into the
+--format html output; must run on raw titles before they're interpolated
+into any tag. */
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/** Computes a GitHub-style anchor slug for a heading title: lowercase,
+strip anything that isn't alphanumeric, a space, or a hyphen, then
+collapse whitespace runs to single hyphens. Repeats of the same slug
+within a file are disambiguated by appending -1, -2, ...
+Note: a title that strips to "" (e.g. "!!!") slugs to the same "" as any
+other such title, so their -1/-2 disambiguation can collide with an
+unrelated title that happens to render to that same string; GitHub's own
+slugger inherits this same ambiguity, so it's left as-is here too. */
+fn slugify(title: &str, seen: &mut HashMap<String, usize>) -> String {
+    let stripped: String = title
+        .chars()
+        .filter(|c| c.is_alphanumeric() || *c == ' ' || *c == '-')
+        .flat_map(|c| c.to_lowercase())
+        .collect();
+    let slug = stripped.split_whitespace().collect::<Vec<_>>().join("-");
+
+    let count = seen.entry(slug.clone()).or_insert(0);
+    let disambiguated = if *count == 0 {
+        slug
+    } else {
+        format!("{}-{}", slug, count)
+    };
+    *count += 1;
+    disambiguated
+}
+
+/** A machine-readable counterpart to the box-drawing tree printed by
+pretty_print; serialized with serde as JSON/YAML for piping a file's
+table of contents into other tooling. `file` is only set on the
+outermost node of a file's output, identifying which document the tree
+belongs to; it's omitted from serialization on every nested node. */
+#[derive(Serialize)]
+struct TocNode {
+    title: String,
+    level: usize,
+    line: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    file: Option<String>,
+    children: Vec<TocNode>,
+}
+
+/** A work-queue-driven preorder walker over a GenTree<Heading>; pops a
+node, then pushes its children back onto the queue so they're visited
+before the popped node's siblings */
+struct NodeIter<'a> {
+    tree: &'a GenTree<Heading>,
+    queue: VecDeque<(Pos<Heading>, usize)>,
+}
+impl<'a> Iterator for NodeIter<'a> {
+    type Item = (usize, &'a Heading);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (position, depth) = self.queue.pop_front()?;
+        let children = self.tree._children(position).expect("invalid position");
+        for &child in children.iter().rev() {
+            self.queue.push_front((child, depth + 1));
+        }
+        let data = self.tree.get(position).expect("missing data");
+        Some((depth, data))
     }
 }
 
@@ -183,6 +349,8 @@ fn parse(root: &Path) -> (String, Vec<Heading>) {
     let mut doc_title = String::new();
     // Regex for capturing headings H1-H6 as #-######
     let h = Regex::new(r"^(#{1,6})\s+(.*)").unwrap();
+    // Regex for opening/closing fence delimiters (```/~~~, 3 or more, up to 3 leading spaces)
+    let fence = Regex::new(r"^\s{0,3}(`{3,}|~{3,})").unwrap();
     let mut headings: Vec<Heading> = Vec::new();
 
     // Read input
@@ -213,12 +381,46 @@ fn parse(root: &Path) -> (String, Vec<Heading>) {
         doc_title.push_str(title);
     }
 
-    // Parse headings line by line
-    for line in content.lines() {
+    // Parse headings line by line, skipping any line that's inside a
+    // fenced (```/~~~) or 4-space indented code block so sample `#`
+    // comments in code don't get captured as headings
+    let mut in_fence = false;
+    let mut fence_marker = '`';
+    let mut fence_len = 0;
+    for (index, line) in content.lines().enumerate() {
+        let line_number = index + 1;
+        if let Some(captures) = fence.captures(line) {
+            let marker_str = captures.get(1).unwrap().as_str();
+            let marker = marker_str.chars().next().unwrap();
+            let len = marker_str.len();
+            if !in_fence {
+                in_fence = true;
+                fence_marker = marker;
+                fence_len = len;
+                continue;
+            } else if marker == fence_marker && len >= fence_len {
+                in_fence = false;
+                continue;
+            }
+            // A fence-looking line of the wrong marker/too short while
+            // already inside a fence is just code content, fall through
+        }
+        if in_fence {
+            continue;
+        }
+        let leading_spaces = line.chars().take_while(|&c| c == ' ').count();
+        if leading_spaces >= 4 {
+            continue;
+        }
+
         if let Some(captures) = h.captures(line) {
             let level = captures.get(1).unwrap().as_str().len();
             let text = captures.get(2).unwrap().as_str().to_string();
-            headings.push(Heading { level, title: text });
+            headings.push(Heading {
+                level,
+                title: text,
+                line: line_number,
+            });
         }
     }
 
@@ -236,12 +438,12 @@ fn construct(level: usize, data: Vec<Heading>) -> GenTree<Heading> {
     for e in data {
         // Creates an owned position for each list entry
         let current_level = e.level; // Keep the borrow checker happy
-        let node: Pos<Heading> = Some(Box::into_raw(Node::build(Some(e))));
+        let node: Pos<Heading> = tree.build(Some(e));
 
         // Case: Adds a child to the current parent and sets level cursor
         if current_level == level_cursor + 1 {
             tree.add_child(position_cursor, node);
-            let data = tree.get(&node).unwrap();
+            let data = tree.get(node).unwrap();
             level_cursor = data.level;
         }
 
@@ -249,14 +451,14 @@ fn construct(level: usize, data: Vec<Heading>) -> GenTree<Heading> {
         else if current_level > level_cursor + 1 {
             let diff = current_level - level_cursor;
             for _ in 1..diff {
-                let heading = Heading::new("[]".to_string(), 0);
-                let placeholder: Pos<Heading> = Some(Box::into_raw(Node::build(Some(heading))));
+                let heading = Heading::new("[]".to_string(), 0, 0);
+                let placeholder: Pos<Heading> = tree.build(Some(heading));
                 tree.add_child(position_cursor, placeholder);
                 position_cursor = placeholder;
                 level_cursor += 1;
             }
             tree.add_child(position_cursor, node);
-            let data = tree.get(&node).unwrap();
+            let data = tree.get(node).unwrap();
             level_cursor = data.level;
         }
 
@@ -275,7 +477,7 @@ fn construct(level: usize, data: Vec<Heading>) -> GenTree<Heading> {
                 level_cursor -= 1;
             }
             tree.add_child(position_cursor, node);
-            let data = tree.get(&node).unwrap();
+            let data = tree.get(node).unwrap();
             level_cursor = data.level;
         }
 
@@ -287,65 +489,84 @@ fn construct(level: usize, data: Vec<Heading>) -> GenTree<Heading> {
 
 /** A wrapper for a recursive preorder(ish) traversal function;
 Contains logic to print [] on empty trees for more appealing presentation */
-fn pretty_print(name: &str, position: &Pos<Heading>) {
-    if let Some(p) = position {
-        let children: &Vec<Pos<Heading>> = unsafe { (*(*p)).children.as_ref() };
-        if children.len() == 0 {
-            println!("ðŸ“„ {}\n\t[]\n", name); // Empty trees
-        } else {
-            println!("ðŸ“„ {}\n\tâ”‚", name);
-            preorder(position, "");
-            println!("");
-        }
+fn pretty_print(name: &str, tree: &GenTree<Heading>, position: Pos<Heading>) {
+    // An empty (sub)tree has nothing for the flattened walk to yield
+    if tree.iter(position).next().is_none() {
+        println!("📄 {}\n\t[]\n", name); // Empty trees
+    } else {
+        println!("📄 {}\n\t│", name);
+        preorder(tree, position, "");
+        println!();
     }
 }
 
-/** Modified preorder traversal function that walks the tree recursively 
+/** Modified preorder traversal function that walks the tree recursively
 printing each node's title and children with appropriate box drawing components */
-fn preorder(position: &Pos<Heading>, prefix: &str) {
-    // Checks that the position (node) exists
-    if let Some(p) = position {
-        // Visit the node at the referenced position
-        let children: &Vec<Pos<Heading>> = unsafe { (*(*p)).children.as_ref() };
-        let mut index = children.len();
-
-        // Recursively visit each child
-        for e in children {
-            let node = Node::get(*e).unwrap();
-            index -= 1;
-            if index == 0 {
-                println!("\t{}â””â”€â”€ {}", prefix, node.title);
-                preorder(e, &format!("{}    ", prefix));
-            } else {
-                println!("\t{}â”œâ”€â”€ {}", prefix, node.title);
-                preorder(e, &format!("{}â”‚Â Â  ", prefix));
-            }
+fn preorder(tree: &GenTree<Heading>, position: Pos<Heading>, prefix: &str) {
+    let children = tree._children(position).expect("invalid position");
+    let mut index = children.len();
+
+    // Recursively visit each child
+    for &child in children {
+        let node = tree.get(child).expect("missing data");
+        index -= 1;
+        if index == 0 {
+            println!("\t{}└── {}", prefix, heading_label(node));
+            preorder(tree, child, &format!("{}    ", prefix));
+        } else {
+            println!("\t{}├── {}", prefix, heading_label(node));
+            preorder(tree, child, &format!("{}│   ", prefix));
         }
+    }
+}
+
+/** Formats a heading's title for tree output, appending its source line
+(e.g. "Geneva (L42)") when it has one; placeholder "[]" nodes have no
+source line and print bare */
+fn heading_label(heading: &Heading) -> String {
+    if heading.line > 0 {
+        format!("{} (L{})", heading.title, heading.line)
     } else {
-        println!("Not a valid position")
+        heading.title.clone()
     }
 }
 
-/** A recursive function that chains the module's utility functions to 
-pretty-print a table of contents for each Markdown file in the specified 
-directory; The is_file() path contains logic to build a tree from filtered 
+/** A recursive function that chains the module's utility functions to
+pretty-print a table of contents for each Markdown file in the specified
+directory; The is_file() path contains logic to build a tree from filtered
 values, skipping headers above the user-supplied level argument;
 The function also substitues the file name (if any) for all MD files
-not formatted with Astro's frontmatter */
-pub fn navigator(level: usize, path: &Path) {
+not formatted with Astro's frontmatter.
+`find`, if given, is a breadcrumb of heading titles (as produced by
+splitting --find on '/'); only the subtree rooted at the matched heading
+is rendered, and files where the path doesn't resolve are skipped.
+When `timing` is set, `stats` accumulates per-file parse/construct
+durations and heading counts, and for the tree and html formats a
+per-file timing line is printed alongside the TOC; the caller is expected to print a final summary from
+`stats` once the whole walk returns. */
+pub fn navigator(
+    level: usize,
+    path: &Path,
+    format: OutputFormat,
+    find: Option<&[&str]>,
+    timing: bool,
+    stats: &mut Stats,
+) {
     if path.is_dir() {
         for e in path.read_dir().expect("read_dir call failed") {
             let entry = e.expect("failure to deconstruct value");
-            navigator(level, &entry.path()); // Recursive call
+            navigator(level, &entry.path(), format, find, timing, stats); // Recursive call
         }
     } else if path.is_file() {
         if let Some(ext) = path.extension() {
-            match ext.to_str() { 
+            match ext.to_str() {
                 Some("md") | Some("mdx") => {
-                    println!("{}", path.display());
+                    let parse_start = Instant::now();
                     let parsed = parse(path);
+                    let parse_elapsed = parse_start.elapsed();
+
                     let mut name: String = parsed.0;
-                    if name == "" {
+                    if name.is_empty() {
                         if let Some(n) = path
                             .file_name()
                             .expect("Error extracting file name")
@@ -354,9 +575,68 @@ pub fn navigator(level: usize, path: &Path) {
                             name = n.to_string()
                         }
                     }
-                    let filtered = parsed.1.into_iter().filter(|h| h.level > level).collect();
+                    let filtered: Vec<Heading> =
+                        parsed.1.into_iter().filter(|h| h.level > level).collect();
+                    let heading_count = filtered.len();
+
+                    let construct_start = Instant::now();
                     let tree = construct(level, filtered);
-                    pretty_print(&name, &tree.root);
+                    let construct_elapsed = construct_start.elapsed();
+
+                    if timing {
+                        stats.files += 1;
+                        stats.headings += heading_count;
+                        stats.parse_time += parse_elapsed;
+                        stats.construct_time += construct_elapsed;
+                    }
+
+                    let position = match find {
+                        Some(segments) => match tree.resolve_path(segments) {
+                            Some(p) => p,
+                            None => return, // Path doesn't resolve in this file; nothing to show
+                        },
+                        None => tree.root,
+                    };
+
+                    // json/yaml are meant to be piped into other tooling, so
+                    // the human-readable path header and timing line (which
+                    // would otherwise break a single file's output as valid
+                    // JSON, and interleave non-JSON lines across a directory
+                    // walk) are only printed for the human-readable formats
+                    let machine_readable =
+                        matches!(format, OutputFormat::Json | OutputFormat::Yaml);
+                    if !machine_readable {
+                        println!("{}", path.display());
+                        if timing {
+                            println!(
+                                "\t[timing] parse: {:?}, construct: {:?}",
+                                parse_elapsed, construct_elapsed
+                            );
+                        }
+                    }
+                    match format {
+                        OutputFormat::Tree => pretty_print(&name, &tree, position),
+                        OutputFormat::Json => {
+                            let mut toc = tree.to_toc_node(position);
+                            toc.file = Some(path.display().to_string());
+                            if position == tree.root {
+                                toc.title = name.clone();
+                            }
+                            println!("{}", serde_json::to_string_pretty(&toc).unwrap());
+                        }
+                        OutputFormat::Yaml => {
+                            let mut toc = tree.to_toc_node(position);
+                            toc.file = Some(path.display().to_string());
+                            if position == tree.root {
+                                toc.title = name.clone();
+                            }
+                            print!("{}", serde_yaml::to_string(&toc).unwrap());
+                        }
+                        OutputFormat::Html => {
+                            let mut seen = HashMap::new();
+                            print!("{}", tree.to_html(position, &mut seen));
+                        }
+                    }
                 }
                 _ => ()
             }
@@ -371,81 +651,259 @@ mod tests{
 
     #[test]
     fn basic_function_test() {
-        use std::ptr; // Used by test
-    
         // Creates a tree with a default ROOT node
         let mut tree = GenTree::<Heading>::new();
-        if let Some(r) = tree.root {
-            if let Some(h) = unsafe { (*r).data.as_ref() } {
-                assert_eq!(&h.title, "ROOT");
-            } else {
-                panic!("Data is None!");
-            }
+        if let Some(h) = tree.get(tree.root) {
+            assert_eq!(&h.title, "ROOT");
+        } else {
+            panic!("Data is None!");
         }
-    
-        // Builds a Heading that simulates an H2, converts it to a Node,
-        // and finally converts it to a position Pos<Heading> as raw pointer "a"
-        let h2 = Heading::new("H2".to_string(), 2);
-        let node_a: Box<Node<Heading>> = Node::build(Some(h2));
-        let node_a_ptr: Pos<Heading> = Some(Box::into_raw(node_a));
-    
+
+        // Builds a Heading that simulates an H2 and allocates it in the arena as "a"
+        let h2 = Heading::new("H2".to_string(), 2, 3);
+        let node_a: Pos<Heading> = tree.build(Some(h2));
+
         // Adds a to root
-        tree.add_child(tree.root, node_a_ptr);
-    
+        tree.add_child(tree.root, node_a);
+
         // Checks that add_child() assigns correct parent for the node
-        assert_eq!(tree.root, tree.parent(node_a_ptr).expect("No parent"));
+        assert_eq!(tree.root, tree.parent(node_a).expect("No parent"));
         // Checks that the parent (ROOT) has exactly one child as the "a" node
-        assert_eq!(tree._children(tree.root), Some(&vec![node_a_ptr]));
+        assert_eq!(tree._children(tree.root), Some(&vec![node_a]));
         // Checks that the ROOT's children list _contains_ the "a" node
-        assert!(tree._children(tree.root).unwrap().iter().any(|&item| {
-            if let Some(ptr) = item {
-                ptr::eq(ptr, node_a_ptr.unwrap())
-            } else {
-                false
-            }
-        }));
-    
+        assert!(tree._children(tree.root).unwrap().contains(&node_a));
+
         // At this point there should be one node with one default ROOT node
         assert_eq!(tree.size, 2);
-    
-        // Builds a Heading that simulates an H3, converts it to a Node,
-        // and finally converts it to a position Pos<Heading> as raw pointer "b"
-        let h3 = Heading::new("H3".to_string(), 3);
-        let node_b: Box<Node<Heading>> = Node::build(Some(h3));
-        let node_b_ptr: Pos<Heading> = Some(Box::into_raw(node_b));
-    
+
+        // Builds a Heading that simulates an H3 and allocates it in the arena as "b"
+        let h3 = Heading::new("H3".to_string(), 3, 5);
+        let node_b: Pos<Heading> = tree.build(Some(h3));
+
         // Adds "b" to "a"
-        tree.add_child(node_a_ptr, node_b_ptr);
-    
+        tree.add_child(node_a, node_b);
+
         // Checks the tree's size, height, and depth of "b"
         // NOTE: size, height, and depth include the ROOT node
         assert_eq!(tree.size, 3);
         assert_eq!(tree._height(tree.root), Some(3));
-        assert_eq!(tree._depth(node_b_ptr), Some(3));
+        assert_eq!(tree._depth(node_b), Some(3));
+    }
+
+    #[test]
+    fn iter_yields_preorder_depths() {
+        let mut tree = GenTree::<Heading>::new();
+
+        let h2 = Heading::new("H2".to_string(), 2, 3);
+        let node_a = tree.build(Some(h2));
+        tree.add_child(tree.root, node_a);
+
+        let h3 = Heading::new("H3".to_string(), 3, 5);
+        let node_b = tree.build(Some(h3));
+        tree.add_child(node_a, node_b);
+
+        let h2_sibling = Heading::new("H2 sibling".to_string(), 2, 7);
+        let node_c = tree.build(Some(h2_sibling));
+        tree.add_child(tree.root, node_c);
+
+        let walked: Vec<(usize, &str)> = tree
+            .iter(tree.root)
+            .map(|(depth, heading)| (depth, heading.title.as_str()))
+            .collect();
+        assert_eq!(
+            walked,
+            vec![(1, "H2"), (2, "H3"), (1, "H2 sibling")]
+        );
+    }
+
+    #[test]
+    fn resolve_path_walks_matching_titles() {
+        let mut tree = GenTree::<Heading>::new();
+
+        let landlocked = tree.build(Some(Heading::new("Landlocked".to_string(), 1, 1)));
+        tree.add_child(tree.root, landlocked);
+
+        let switzerland = tree.build(Some(Heading::new("Switzerland".to_string(), 2, 2)));
+        tree.add_child(landlocked, switzerland);
+
+        let geneva = tree.build(Some(Heading::new("Geneva".to_string(), 3, 3)));
+        tree.add_child(switzerland, geneva);
+
+        assert_eq!(
+            tree.resolve_path(&["Landlocked", "Switzerland", "Geneva"]),
+            Some(geneva)
+        );
+        assert_eq!(tree.resolve_path(&["Landlocked", "Bolivia"]), None);
+        assert_eq!(tree.resolve_path(&[]), Some(tree.root));
+    }
+
+    /** Writes `content` to a uniquely-named file under the system temp
+    dir so parse() has a real path to read, returning that path for the
+    caller to pass in (and clean up) */
+    fn write_temp_md(name: &str, content: &str) -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(format!("mdtree_test_{}_{}.md", name, std::process::id()));
+        std::fs::write(&path, content).expect("failed to write temp fixture");
+        path
     }
-    
+
+    #[test]
+    fn parse_skips_headings_inside_fenced_code() {
+        let path = write_temp_md(
+            "fenced",
+            "# Real Heading\n```\n# not a heading\n```\n## Also Real\n",
+        );
+        let (_, headings) = parse(&path);
+        std::fs::remove_file(&path).ok();
+        let titles: Vec<&str> = headings.iter().map(|h| h.title.as_str()).collect();
+        assert_eq!(titles, vec!["Real Heading", "Also Real"]);
+    }
+
+    #[test]
+    fn parse_treats_unterminated_fence_as_code_to_eof() {
+        let path = write_temp_md(
+            "unterminated",
+            "# Real Heading\n```\n# not a heading\n## also not a heading\n",
+        );
+        let (_, headings) = parse(&path);
+        std::fs::remove_file(&path).ok();
+        let titles: Vec<&str> = headings.iter().map(|h| h.title.as_str()).collect();
+        assert_eq!(titles, vec!["Real Heading"]);
+    }
+
+    #[test]
+    fn parse_requires_matching_fence_marker_and_length() {
+        // A ~~~ line doesn't close a ``` fence, so the # inside stays code;
+        // the closing ``` does close it, and the following # is real again
+        let path = write_temp_md(
+            "mismatch",
+            "```\n~~~\n# not a heading\n```\n# Real Heading\n",
+        );
+        let (_, headings) = parse(&path);
+        std::fs::remove_file(&path).ok();
+        let titles: Vec<&str> = headings.iter().map(|h| h.title.as_str()).collect();
+        assert_eq!(titles, vec!["Real Heading"]);
+    }
+
+    #[test]
+    fn parse_skips_four_space_indented_headings() {
+        let path = write_temp_md(
+            "indented",
+            "# Real Heading\n    # not a heading\n## Also Real\n",
+        );
+        let (_, headings) = parse(&path);
+        std::fs::remove_file(&path).ok();
+        let titles: Vec<&str> = headings.iter().map(|h| h.title.as_str()).collect();
+        assert_eq!(titles, vec!["Real Heading", "Also Real"]);
+    }
+
+    #[test]
+    fn slugify_disambiguates_repeated_titles() {
+        let mut seen = HashMap::new();
+        assert_eq!(slugify("Foo", &mut seen), "foo");
+        assert_eq!(slugify("Foo", &mut seen), "foo-1");
+        assert_eq!(slugify("Foo", &mut seen), "foo-2");
+    }
+
+    #[test]
+    fn escape_html_escapes_special_characters() {
+        assert_eq!(
+            escape_html("<script>alert(1)</script>"),
+            "&lt;script&gt;alert(1)&lt;/script&gt;"
+        );
+        assert_eq!(escape_html("Foo & Bar \"baz\""), "Foo &amp; Bar &quot;baz&quot;");
+    }
+
+    #[test]
+    fn to_html_escapes_heading_titles() {
+        let mut tree = GenTree::<Heading>::new();
+        let heading = tree.build(Some(Heading::new(
+            "<script>alert(1)</script>".to_string(),
+            1,
+            1,
+        )));
+        tree.add_child(tree.root, heading);
+
+        let mut seen = HashMap::new();
+        let html = tree.to_html(tree.root, &mut seen);
+        assert!(html.contains("&lt;script&gt;alert(1)&lt;/script&gt;"));
+        assert!(!html.contains("<script>alert(1)</script>"));
+    }
+
+    #[test]
+    fn parse_records_real_source_line_numbers() {
+        let path = write_temp_md(
+            "line_numbers",
+            "---\ntitle: Doc Title\n---\nIntro text\n\n# First\n\nSome body text\n\n## Second\n",
+        );
+        let (_, headings) = parse(&path);
+        std::fs::remove_file(&path).ok();
+        let lines: Vec<(&str, usize)> =
+            headings.iter().map(|h| (h.title.as_str(), h.line)).collect();
+        assert_eq!(lines, vec![("First", 6), ("Second", 10)]);
+    }
+
+    #[test]
+    fn to_toc_node_round_trips_level_and_line() {
+        let mut tree = GenTree::<Heading>::new();
+
+        let landlocked = tree.build(Some(Heading::new("Landlocked".to_string(), 1, 10)));
+        tree.add_child(tree.root, landlocked);
+
+        let switzerland = tree.build(Some(Heading::new("Switzerland".to_string(), 2, 20)));
+        tree.add_child(landlocked, switzerland);
+
+        let toc = tree.to_toc_node(tree.root);
+        assert_eq!(toc.children.len(), 1);
+        assert_eq!(toc.children[0].title, "Landlocked");
+        assert_eq!(toc.children[0].level, 1);
+        assert_eq!(toc.children[0].line, 10);
+        assert_eq!(toc.children[0].children[0].title, "Switzerland");
+        assert_eq!(toc.children[0].children[0].level, 2);
+        assert_eq!(toc.children[0].children[0].line, 20);
+    }
+
+    #[test]
+    fn stats_accumulate_across_files() {
+        let mut stats = Stats::default();
+
+        stats.files += 1;
+        stats.headings += 3;
+        stats.parse_time += Duration::from_millis(5);
+        stats.construct_time += Duration::from_millis(2);
+
+        stats.files += 1;
+        stats.headings += 2;
+        stats.parse_time += Duration::from_millis(7);
+        stats.construct_time += Duration::from_millis(1);
+
+        assert_eq!(stats.files, 2);
+        assert_eq!(stats.headings, 5);
+        assert_eq!(stats.parse_time, Duration::from_millis(12));
+        assert_eq!(stats.construct_time, Duration::from_millis(3));
+    }
+
     #[test]
     /** Creates this tree to test properties
         [] Lorem Ipsum Test
-        â”‚    An ordered look at MD parsing
-        â”‚
-        â”œâ”€â”€ Landlocked
-        â”‚ Â Â â”œâ”€â”€ Switzerland
-        â”‚   â”‚ Â Â â””â”€â”€ Geneva
-        â”‚   â”‚     Â Â â””â”€â”€ Old Town
-        â”‚   â”‚     Â Â     â””â”€â”€ CathÃ©drale Saint-Pierre
-        â”‚ Â Â â””â”€â”€ Bolivia
-        â””â”€â”€ Island
-          â”œâ”€â”€ Marine
-          â”‚ Â Â â””â”€â”€ Australiae
-          â””â”€â”€ Fresh Water
+        │    An ordered look at MD parsing
+        │
+        ├── Landlocked
+        │   ├── Switzerland
+        │   │   └── Geneva
+        │   │       └── Old Town
+        │   │           └── Cathédrale Saint-Pierre
+        │   └── Bolivia
+        └── Island
+          ├── Marine
+          │   └── Australiae
+          └── Fresh Water
     */
     fn n_ary_algorithm_test() {
-    
+
         // Checks that the height is 4
-    
+
         // Checks that the depth of the H5 is 4
-    
+
         // Empty doc test
     }
 }
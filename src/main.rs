@@ -1,4 +1,5 @@
 use clap::Parser;
+use mdtree::OutputFormat;
 use std::path::PathBuf; // Imports lib.rs
 
 #[derive(Parser)]
@@ -16,9 +17,43 @@ struct Args {
     /// Relative path to MD doc or dir
     #[arg(short, long, default_value = ".")]
     path: PathBuf,
+
+    /// Table of contents output representation
+    #[arg(
+        short,
+        long,
+        value_enum,
+        default_value_t = OutputFormat::Tree,
+        help = "tree prints box-drawing art; json/yaml emit a machine-readable TOC; html emits a nested <ul>/<li> TOC with anchor links."
+    )]
+    format: OutputFormat,
+
+    /// Print only the subtree rooted at the heading found by following
+    /// this '/'-separated breadcrumb of titles, e.g. "Landlocked/Switzerland/Geneva"
+    #[arg(long)]
+    find: Option<String>,
+
+    /// Report per-file parse/construct timing and a final summary
+    #[arg(long)]
+    timing: bool,
 }
 
 fn main() {
     let args = Args::parse();
-    mdtree::navigator(args.level, &args.path);
+    let find: Option<Vec<&str>> = args.find.as_deref().map(|f| f.split('/').collect());
+    let mut stats = mdtree::Stats::default();
+    mdtree::navigator(
+        args.level,
+        &args.path,
+        args.format,
+        find.as_deref(),
+        args.timing,
+        &mut stats,
+    );
+    if args.timing {
+        println!(
+            "\nSummary: {} file(s), {} heading(s), parse {:?}, construct {:?}",
+            stats.files, stats.headings, stats.parse_time, stats.construct_time
+        );
+    }
 }